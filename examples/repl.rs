@@ -0,0 +1,84 @@
+//! A small REPL: each line is `<expr>; <symbol>=<value>, ...; wrt=<symbol>`.
+//! It prints the parsed expression, its value at the given point, and its
+//! derivative (both symbolically and evaluated at that point).
+//!
+//! Example:
+//!     (x + 1) * (x + 1); x=2; wrt=x
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use genericalc::{parse, Eval, Inputs, Symbol};
+
+fn main() {
+    let stdin = io::stdin();
+    print!("> ");
+    io::stdout().flush().ok();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            print!("> ");
+            io::stdout().flush().ok();
+            continue;
+        }
+
+        match run_line(&line) {
+            Ok(()) => {}
+            Err(msg) => println!("error: {}", msg),
+        }
+
+        print!("> ");
+        io::stdout().flush().ok();
+    }
+}
+
+fn run_line(line: &str) -> Result<(), String> {
+    let mut parts = line.split(';');
+    let expr_src = parts.next().unwrap_or("").trim();
+    let assign_src = parts.next().unwrap_or("").trim();
+    let wrt_src = parts.next().unwrap_or("").trim();
+
+    let expr = parse(expr_src).map_err(|e| e.to_string())?;
+
+    let mut symbol_map = HashMap::new();
+    for assign in assign_src.split(',') {
+        let assign = assign.trim();
+        if assign.is_empty() {
+            continue;
+        }
+        let (name, val) = assign
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=value, got '{}'", assign))?;
+        let val: f64 = val
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid number '{}'", val.trim()))?;
+        symbol_map.insert(Symbol::new(name.trim()), val);
+    }
+
+    let wrt = wrt_src
+        .strip_prefix("wrt=")
+        .ok_or_else(|| format!("expected 'wrt=<symbol>', got '{}'", wrt_src))?
+        .trim();
+    let wrt = Symbol::new(wrt);
+
+    for sym in expr.symbols() {
+        if !symbol_map.contains_key(&sym) {
+            return Err(format!("unbound symbol '{}'", sym));
+        }
+    }
+
+    let inputs = Inputs { symbol_map };
+    let ddx = expr.try_deriv(&wrt).map_err(|e| e.to_string())?.simplify();
+
+    println!("parsed:   {}", expr);
+    println!("value:    {}", expr.eval(&inputs));
+    println!("deriv:    {}", ddx);
+    println!("d/d{}:   {}", wrt, ddx.eval(&inputs));
+
+    Ok(())
+}
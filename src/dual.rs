@@ -0,0 +1,335 @@
+use std::ops;
+
+use crate::expr::{Expr, NonConstantExponentError};
+use crate::nodes::{Const, DAdd, DCos, DDiv, DExp, DLn, DMul, DNeg, DPow, DSin, DSub};
+use crate::symbol::{Eval, Inputs, Symbol};
+
+/// A value paired with its derivative with respect to one seed variable,
+/// following the usual dual-number arithmetic (`eps^2 == 0`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual {
+    pub val: f64,
+    pub eps: f64,
+}
+
+impl Dual {
+    pub fn constant(val: f64) -> Dual {
+        Dual { val, eps: 0.0 }
+    }
+
+    pub fn variable(val: f64) -> Dual {
+        Dual { val, eps: 1.0 }
+    }
+
+    pub fn exp(self) -> Dual {
+        let val = self.val.exp();
+        Dual {
+            val,
+            eps: self.eps * val,
+        }
+    }
+
+    pub fn ln(self) -> Dual {
+        Dual {
+            val: self.val.ln(),
+            eps: self.eps / self.val,
+        }
+    }
+
+    pub fn sin(self) -> Dual {
+        Dual {
+            val: self.val.sin(),
+            eps: self.eps * self.val.cos(),
+        }
+    }
+
+    pub fn cos(self) -> Dual {
+        Dual {
+            val: self.val.cos(),
+            eps: -self.eps * self.val.sin(),
+        }
+    }
+
+    /// Only a constant exponent is supported, matching the restriction on
+    /// `DPow<L, Const>::deriv` and `Expr::Pow`'s symbolic derivative.
+    pub fn powf(self, n: f64) -> Dual {
+        Dual {
+            val: self.val.powf(n),
+            eps: self.eps * n * self.val.powf(n - 1.0),
+        }
+    }
+}
+
+impl ops::Add for Dual {
+    type Output = Dual;
+    fn add(self, rhs: Dual) -> Dual {
+        Dual {
+            val: self.val + rhs.val,
+            eps: self.eps + rhs.eps,
+        }
+    }
+}
+
+impl ops::Sub for Dual {
+    type Output = Dual;
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual {
+            val: self.val - rhs.val,
+            eps: self.eps - rhs.eps,
+        }
+    }
+}
+
+impl ops::Mul for Dual {
+    type Output = Dual;
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual {
+            val: self.val * rhs.val,
+            eps: self.eps * rhs.val + self.val * rhs.eps,
+        }
+    }
+}
+
+impl ops::Div for Dual {
+    type Output = Dual;
+    fn div(self, rhs: Dual) -> Dual {
+        Dual {
+            val: self.val / rhs.val,
+            eps: (self.eps * rhs.val - self.val * rhs.eps) / (rhs.val * rhs.val),
+        }
+    }
+}
+
+impl ops::Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual {
+            val: -self.val,
+            eps: -self.eps,
+        }
+    }
+}
+
+/// Forward-mode automatic differentiation: computes a node's value and its
+/// derivative with respect to `wrt` in one traversal, without building a
+/// symbolic `DerivT`/`Expr` tree first.
+pub trait EvalDual: Eval {
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual;
+}
+
+impl EvalDual for Const {
+    fn eval_dual(&self, _inputs: &Inputs, _wrt: &Symbol) -> Dual {
+        Dual::constant(self.val)
+    }
+}
+
+impl EvalDual for Symbol {
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        let val = self.eval(inputs);
+        if self == wrt {
+            Dual::variable(val)
+        } else {
+            Dual::constant(val)
+        }
+    }
+}
+
+impl<L, R> EvalDual for DAdd<L, R>
+where
+    L: EvalDual,
+    R: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.lhs.eval_dual(inputs, wrt) + self.rhs.eval_dual(inputs, wrt)
+    }
+}
+
+impl<L, R> EvalDual for DSub<L, R>
+where
+    L: EvalDual,
+    R: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.lhs.eval_dual(inputs, wrt) - self.rhs.eval_dual(inputs, wrt)
+    }
+}
+
+impl<L, R> EvalDual for DMul<L, R>
+where
+    L: EvalDual,
+    R: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.lhs.eval_dual(inputs, wrt) * self.rhs.eval_dual(inputs, wrt)
+    }
+}
+
+impl<L, R> EvalDual for DDiv<L, R>
+where
+    L: EvalDual,
+    R: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.lhs.eval_dual(inputs, wrt) / self.rhs.eval_dual(inputs, wrt)
+    }
+}
+
+impl<L> EvalDual for DPow<L, Const>
+where
+    L: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.lhs.eval_dual(inputs, wrt).powf(self.rhs.val)
+    }
+}
+
+impl<A> EvalDual for DNeg<A>
+where
+    A: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        -self.arg.eval_dual(inputs, wrt)
+    }
+}
+
+impl<A> EvalDual for DExp<A>
+where
+    A: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.arg.eval_dual(inputs, wrt).exp()
+    }
+}
+
+impl<A> EvalDual for DLn<A>
+where
+    A: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.arg.eval_dual(inputs, wrt).ln()
+    }
+}
+
+impl<A> EvalDual for DSin<A>
+where
+    A: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.arg.eval_dual(inputs, wrt).sin()
+    }
+}
+
+impl<A> EvalDual for DCos<A>
+where
+    A: EvalDual,
+{
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.arg.eval_dual(inputs, wrt).cos()
+    }
+}
+
+impl Expr {
+    /// Same rules as [`EvalDual::eval_dual`], but reports a non-constant
+    /// `Pow` exponent as an error instead of panicking -- see
+    /// [`Expr::try_deriv`] for why `Expr` can't rule this out statically
+    /// the way `DPow<L, Const>` does.
+    pub fn try_eval_dual(
+        &self,
+        inputs: &Inputs,
+        wrt: &Symbol,
+    ) -> Result<Dual, NonConstantExponentError> {
+        Ok(match self {
+            Expr::Const(val) => Dual::constant(*val),
+            Expr::Symbol(sym) => sym.eval_dual(inputs, wrt),
+            Expr::Add(lhs, rhs) => lhs.try_eval_dual(inputs, wrt)? + rhs.try_eval_dual(inputs, wrt)?,
+            Expr::Sub(lhs, rhs) => lhs.try_eval_dual(inputs, wrt)? - rhs.try_eval_dual(inputs, wrt)?,
+            Expr::Mul(lhs, rhs) => lhs.try_eval_dual(inputs, wrt)? * rhs.try_eval_dual(inputs, wrt)?,
+            Expr::Div(lhs, rhs) => lhs.try_eval_dual(inputs, wrt)? / rhs.try_eval_dual(inputs, wrt)?,
+            Expr::Pow(lhs, rhs) => match rhs.as_ref() {
+                Expr::Const(n) => lhs.try_eval_dual(inputs, wrt)?.powf(*n),
+                _ => return Err(NonConstantExponentError),
+            },
+            Expr::Neg(arg) => -arg.try_eval_dual(inputs, wrt)?,
+            Expr::Exp(arg) => arg.try_eval_dual(inputs, wrt)?.exp(),
+            Expr::Ln(arg) => arg.try_eval_dual(inputs, wrt)?.ln(),
+            Expr::Sin(arg) => arg.try_eval_dual(inputs, wrt)?.sin(),
+            Expr::Cos(arg) => arg.try_eval_dual(inputs, wrt)?.cos(),
+        })
+    }
+}
+
+impl EvalDual for Expr {
+    /// Panics on a non-constant `Pow` exponent; use
+    /// [`Expr::try_eval_dual`] when that can't be ruled out statically.
+    fn eval_dual(&self, inputs: &Inputs, wrt: &Symbol) -> Dual {
+        self.try_eval_dual(inputs, wrt)
+            .expect("differentiating x^y requires a constant exponent")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::Derive;
+
+    fn x() -> Expr {
+        Expr::Symbol(Symbol::new("x"))
+    }
+
+    fn inputs_at(val: f64) -> Inputs {
+        Inputs {
+            symbol_map: [(Symbol::new("x"), val)].into_iter().collect(),
+        }
+    }
+
+    /// Checks that forward-mode `eval_dual` agrees with symbolic `deriv`
+    /// (evaluated at the same point) for `expr`, at every point in `at`.
+    fn assert_matches_symbolic_deriv(expr: Expr, at: &[f64]) {
+        let wrt = Symbol::new("x");
+        let symbolic = expr.deriv(&wrt);
+        for &val in at {
+            let inputs = inputs_at(val);
+            let dual = expr.eval_dual(&inputs, &wrt);
+            assert_eq!(dual.val, expr.eval(&inputs));
+            let expected_eps = symbolic.eval(&inputs);
+            assert!(
+                (dual.eps - expected_eps).abs() < 1e-9,
+                "at x={}: dual gave {}, symbolic deriv gave {}",
+                val,
+                dual.eps,
+                expected_eps
+            );
+        }
+    }
+
+    #[test]
+    fn matches_symbolic_deriv_for_polynomial() {
+        // x^3 + 2x
+        let expr = x().pow(Expr::Const(3.0)) + Expr::Const(2.0) * x();
+        assert_matches_symbolic_deriv(expr, &[-2.0, 0.5, 3.0]);
+    }
+
+    #[test]
+    fn matches_symbolic_deriv_for_quotient() {
+        // x / (x + 1)
+        let expr = x() / (x() + Expr::Const(1.0));
+        assert_matches_symbolic_deriv(expr, &[2.0, 5.0]);
+    }
+
+    #[test]
+    fn matches_symbolic_deriv_for_transcendental_composition() {
+        // sin(exp(x)) - ln(x)
+        let expr = Expr::Sin(Box::new(Expr::Exp(Box::new(x())))) - Expr::Ln(Box::new(x()));
+        assert_matches_symbolic_deriv(expr, &[0.5, 1.5]);
+    }
+
+    #[test]
+    fn try_eval_dual_rejects_non_constant_exponent() {
+        let expr = x().pow(x());
+        let inputs = inputs_at(2.0);
+        assert_eq!(
+            expr.try_eval_dual(&inputs, &Symbol::new("x")),
+            Err(NonConstantExponentError)
+        );
+    }
+}
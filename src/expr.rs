@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::ops;
+
+use crate::symbol::{Derive, Eval, Inputs, Symbol};
+
+/// Differentiating `x^y` needs a constant `y`, matching the restriction
+/// that `DPow<L, R>` in [`crate::nodes`] only implements `Derive` for
+/// `R = Const`. For the typed nodes that's enforced at compile time; for
+/// `Expr` the exponent's shape is only known at runtime, so it's a
+/// recoverable error instead of a panic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NonConstantExponentError;
+
+impl fmt::Display for NonConstantExponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "differentiating x^y requires a constant exponent")
+    }
+}
+
+impl std::error::Error for NonConstantExponentError {}
+
+/// A heap-allocated, runtime expression tree. Unlike the generic `D*` nodes
+/// in [`crate::nodes`], whose shape is fixed at compile time, an `Expr` can
+/// be built from data the program only sees at runtime -- e.g. a string
+/// read from stdin via [`crate::parser::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(f64),
+    Symbol(Symbol),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+    Exp(Box<Expr>),
+    Ln(Box<Expr>),
+    Sin(Box<Expr>),
+    Cos(Box<Expr>),
+}
+
+impl Expr {
+    /// There's no `std::ops` trait for exponentiation, so `pow` stays a
+    /// plain method alongside the operator overloads below.
+    pub fn pow(self, rhs: Expr) -> Expr {
+        Expr::Pow(Box::new(self), Box::new(rhs))
+    }
+
+    /// Folds constants and removes additive/multiplicative identities,
+    /// see [`crate::simplify`].
+    pub fn simplify(&self) -> Expr {
+        crate::simplify::simplify(self)
+    }
+
+    /// Every partial derivative of `self`, one per symbol in `vars`, in the
+    /// order given.
+    pub fn gradient(&self, vars: &[Symbol]) -> Result<Vec<Expr>, NonConstantExponentError> {
+        vars.iter().map(|v| self.try_deriv(v)).collect()
+    }
+
+    /// Differentiates `self` with respect to `var`, `n` times in a row.
+    pub fn nth_deriv(&self, var: &Symbol, n: usize) -> Result<Expr, NonConstantExponentError> {
+        let mut result = self.clone();
+        for _ in 0..n {
+            result = result.try_deriv(var)?;
+        }
+        Ok(result)
+    }
+
+    /// Every symbol referenced anywhere in `self`.
+    pub fn symbols(&self) -> HashSet<Symbol> {
+        let mut out = HashSet::new();
+        self.collect_symbols(&mut out);
+        out
+    }
+
+    fn collect_symbols(&self, out: &mut HashSet<Symbol>) {
+        match self {
+            Expr::Const(_) => {}
+            Expr::Symbol(sym) => {
+                out.insert(sym.clone());
+            }
+            Expr::Add(lhs, rhs)
+            | Expr::Sub(lhs, rhs)
+            | Expr::Mul(lhs, rhs)
+            | Expr::Div(lhs, rhs)
+            | Expr::Pow(lhs, rhs) => {
+                lhs.collect_symbols(out);
+                rhs.collect_symbols(out);
+            }
+            Expr::Neg(arg) | Expr::Exp(arg) | Expr::Ln(arg) | Expr::Sin(arg) | Expr::Cos(arg) => {
+                arg.collect_symbols(out);
+            }
+        }
+    }
+
+    /// Same rules as [`Derive::deriv`], but reports a non-constant `Pow`
+    /// exponent as an error instead of panicking -- the shape of a
+    /// runtime-parsed `Expr` isn't known until this point, unlike the typed
+    /// `D*` nodes where it's rejected at compile time.
+    pub fn try_deriv(&self, vs: &Symbol) -> Result<Expr, NonConstantExponentError> {
+        Ok(match self {
+            Expr::Const(_) => Expr::Const(0.0),
+            Expr::Symbol(sym) => Expr::Const(if sym == vs { 1.0 } else { 0.0 }),
+            Expr::Add(lhs, rhs) => lhs.try_deriv(vs)? + rhs.try_deriv(vs)?,
+            Expr::Sub(lhs, rhs) => lhs.try_deriv(vs)? - rhs.try_deriv(vs)?,
+            Expr::Mul(lhs, rhs) => {
+                let l = (**lhs).clone();
+                let r = (**rhs).clone();
+                l * rhs.try_deriv(vs)? + lhs.try_deriv(vs)? * r
+            }
+            Expr::Div(lhs, rhs) => {
+                let l = (**lhs).clone();
+                let r = (**rhs).clone();
+                let num = lhs.try_deriv(vs)? * r.clone() - l * rhs.try_deriv(vs)?;
+                let den = r.clone() * r;
+                num / den
+            }
+            Expr::Pow(lhs, rhs) => match rhs.as_ref() {
+                Expr::Const(n) => {
+                    Expr::Const(*n) * (**lhs).clone().pow(Expr::Const(n - 1.0)) * lhs.try_deriv(vs)?
+                }
+                _ => return Err(NonConstantExponentError),
+            },
+            Expr::Neg(arg) => -arg.try_deriv(vs)?,
+            Expr::Exp(arg) => Expr::Exp(arg.clone()) * arg.try_deriv(vs)?,
+            Expr::Ln(arg) => Expr::Const(1.0) / (**arg).clone() * arg.try_deriv(vs)?,
+            Expr::Sin(arg) => Expr::Cos(arg.clone()) * arg.try_deriv(vs)?,
+            Expr::Cos(arg) => -Expr::Sin(arg.clone()) * arg.try_deriv(vs)?,
+        })
+    }
+}
+
+/// The Jacobian of `funcs`: one row per function, one column per symbol in
+/// `vars`, i.e. `jacobian(funcs, vars)[i][j] == funcs[i].deriv(&vars[j])`.
+pub fn jacobian(
+    funcs: &[Expr],
+    vars: &[Symbol],
+) -> Result<Vec<Vec<Expr>>, NonConstantExponentError> {
+    funcs.iter().map(|f| f.gradient(vars)).collect()
+}
+
+impl ops::Add for Expr {
+    type Output = Expr;
+    fn add(self, rhs: Expr) -> Expr {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl ops::Sub for Expr {
+    type Output = Expr;
+    fn sub(self, rhs: Expr) -> Expr {
+        Expr::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl ops::Mul for Expr {
+    type Output = Expr;
+    fn mul(self, rhs: Expr) -> Expr {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl ops::Div for Expr {
+    type Output = Expr;
+    fn div(self, rhs: Expr) -> Expr {
+        Expr::Div(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl ops::Neg for Expr {
+    type Output = Expr;
+    fn neg(self) -> Expr {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(val) => write!(f, "{}", val),
+            Expr::Symbol(sym) => write!(f, "{}", sym),
+            Expr::Add(lhs, rhs) => write!(f, "({} + {})", lhs, rhs),
+            Expr::Sub(lhs, rhs) => write!(f, "({} - {})", lhs, rhs),
+            Expr::Mul(lhs, rhs) => write!(f, "({} * {})", lhs, rhs),
+            Expr::Div(lhs, rhs) => write!(f, "({} / {})", lhs, rhs),
+            Expr::Pow(lhs, rhs) => write!(f, "({} ^ {})", lhs, rhs),
+            Expr::Neg(arg) => write!(f, "(-{})", arg),
+            Expr::Exp(arg) => write!(f, "exp({})", arg),
+            Expr::Ln(arg) => write!(f, "ln({})", arg),
+            Expr::Sin(arg) => write!(f, "sin({})", arg),
+            Expr::Cos(arg) => write!(f, "cos({})", arg),
+        }
+    }
+}
+
+impl Eval for Expr {
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        match self {
+            Expr::Const(val) => *val,
+            Expr::Symbol(sym) => sym.eval(inputs),
+            Expr::Add(lhs, rhs) => lhs.eval(inputs) + rhs.eval(inputs),
+            Expr::Sub(lhs, rhs) => lhs.eval(inputs) - rhs.eval(inputs),
+            Expr::Mul(lhs, rhs) => lhs.eval(inputs) * rhs.eval(inputs),
+            Expr::Div(lhs, rhs) => lhs.eval(inputs) / rhs.eval(inputs),
+            Expr::Pow(lhs, rhs) => lhs.eval(inputs).powf(rhs.eval(inputs)),
+            Expr::Neg(arg) => -arg.eval(inputs),
+            Expr::Exp(arg) => arg.eval(inputs).exp(),
+            Expr::Ln(arg) => arg.eval(inputs).ln(),
+            Expr::Sin(arg) => arg.eval(inputs).sin(),
+            Expr::Cos(arg) => arg.eval(inputs).cos(),
+        }
+    }
+}
+
+impl Derive for Expr {
+    type DerivT = Expr;
+
+    /// Panics on a non-constant `Pow` exponent; callers that can't
+    /// guarantee that statically (e.g. anything differentiating a
+    /// runtime-parsed `Expr`) should use [`Expr::try_deriv`] instead.
+    fn deriv(&self, vs: &Symbol) -> Expr {
+        self.try_deriv(vs)
+            .expect("differentiating x^y requires a constant exponent")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Expr {
+        Expr::Symbol(Symbol::new("x"))
+    }
+
+    fn y() -> Expr {
+        Expr::Symbol(Symbol::new("y"))
+    }
+
+    fn inputs_at(assignments: &[(&str, f64)]) -> Inputs {
+        Inputs {
+            symbol_map: assignments
+                .iter()
+                .map(|(name, val)| (Symbol::new(name), *val))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn gradient_has_one_entry_per_var_in_order() {
+        // f(x, y) = x^2 * y, df/dx = 2xy, df/dy = x^2
+        let f = x().pow(Expr::Const(2.0)) * y();
+        let vars = [Symbol::new("x"), Symbol::new("y")];
+        let grad = f.gradient(&vars).unwrap();
+        let inputs = inputs_at(&[("x", 3.0), ("y", 5.0)]);
+
+        assert_eq!(grad.len(), 2);
+        assert_eq!(grad[0].eval(&inputs), 2.0 * 3.0 * 5.0);
+        assert_eq!(grad[1].eval(&inputs), 3.0f64.powf(2.0));
+    }
+
+    #[test]
+    fn gradient_propagates_non_constant_exponent_error() {
+        // f(x, y) = x^y has no constant exponent to differentiate against.
+        let f = x().pow(y());
+        let vars = [Symbol::new("x")];
+        assert_eq!(f.gradient(&vars), Err(NonConstantExponentError));
+    }
+
+    #[test]
+    fn nth_deriv_applies_deriv_repeatedly() {
+        // f(x) = x^3, f'' = 6x
+        let f = x().pow(Expr::Const(3.0));
+        let second = f.nth_deriv(&Symbol::new("x"), 2).unwrap();
+        let inputs = inputs_at(&[("x", 4.0)]);
+        assert_eq!(second.eval(&inputs), 6.0 * 4.0);
+    }
+
+    #[test]
+    fn nth_deriv_zero_times_is_identity() {
+        let f = x() * y();
+        let same = f.clone().nth_deriv(&Symbol::new("x"), 0).unwrap();
+        assert_eq!(same, f);
+    }
+
+    #[test]
+    fn jacobian_rows_match_gradients_of_each_function() {
+        // f0(x, y) = x + y, f1(x, y) = x * y
+        let funcs = [x() + y(), x() * y()];
+        let vars = [Symbol::new("x"), Symbol::new("y")];
+        let jac = jacobian(&funcs, &vars).unwrap();
+        let inputs = inputs_at(&[("x", 2.0), ("y", 7.0)]);
+
+        assert_eq!(jac.len(), 2);
+        assert_eq!(jac[0][0].eval(&inputs), 1.0);
+        assert_eq!(jac[0][1].eval(&inputs), 1.0);
+        assert_eq!(jac[1][0].eval(&inputs), 7.0);
+        assert_eq!(jac[1][1].eval(&inputs), 2.0);
+    }
+
+    #[test]
+    fn symbols_collects_every_referenced_symbol() {
+        let expr = x().pow(Expr::Const(2.0)) + Expr::Sin(Box::new(y())) - x();
+        let mut names: Vec<String> = expr.symbols().into_iter().map(|s| s.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["x".to_string(), "y".to_string()]);
+    }
+}
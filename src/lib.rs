@@ -0,0 +1,12 @@
+pub mod dual;
+pub mod expr;
+pub mod nodes;
+pub mod parser;
+pub mod simplify;
+pub mod symbol;
+
+pub use dual::{Dual, EvalDual};
+pub use expr::{jacobian, Expr, NonConstantExponentError};
+pub use nodes::{Const, DAdd, DCos, DDiv, DExp, DLn, DMul, DNeg, DPow, DSin, DSub};
+pub use parser::{parse, ParseError};
+pub use symbol::{Derive, Eval, Inputs, Symbol};
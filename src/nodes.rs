@@ -0,0 +1,528 @@
+use std::fmt;
+use std::ops;
+
+use crate::symbol::{Derive, Eval, Inputs, Symbol};
+
+#[derive(Debug, Clone)]
+pub struct Const {
+    pub val: f64,
+}
+
+impl From<f64> for Const {
+    fn from(val: f64) -> Self {
+        Const { val }
+    }
+}
+
+impl fmt::Display for Const {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl Eval for Const {
+    fn eval(&self, _inputs: &Inputs) -> f64 {
+        self.val
+    }
+}
+impl Derive for Const {
+    type DerivT = Const;
+    fn deriv(&self, _vs: &Symbol) -> Self::DerivT {
+        Const { val: 0.0 }
+    }
+}
+
+impl Derive for Symbol {
+    type DerivT = Const;
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        Const {
+            val: if vs == self { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DAdd<L, R> {
+    pub lhs: L,
+    pub rhs: R,
+}
+
+impl<L, R> fmt::Display for DAdd<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} + {})", self.lhs, self.rhs)
+    }
+}
+
+impl<L, R> Eval for DAdd<L, R>
+where
+    L: Eval,
+    R: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.lhs.eval(inputs) + self.rhs.eval(inputs)
+    }
+}
+
+impl<L, R> Derive for DAdd<L, R>
+where
+    L: Derive,
+    R: Derive,
+{
+    type DerivT = DAdd<L::DerivT, R::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DAdd {
+            lhs: self.lhs.deriv(vs),
+            rhs: self.rhs.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DMul<L, R> {
+    pub lhs: L,
+    pub rhs: R,
+}
+
+impl<L, R> fmt::Display for DMul<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} * {})", self.lhs, self.rhs)
+    }
+}
+
+impl<L, R> Eval for DMul<L, R>
+where
+    L: Eval,
+    R: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.lhs.eval(inputs) * self.rhs.eval(inputs)
+    }
+}
+impl<L, R> Derive for DMul<L, R>
+where
+    L: Derive,
+    R: Derive,
+{
+    type DerivT = DAdd<DMul<L, R::DerivT>, DMul<L::DerivT, R>>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        let d_l = self.lhs.deriv(vs);
+        let d_r = self.rhs.deriv(vs);
+        DAdd {
+            lhs: DMul {
+                lhs: self.lhs.clone(),
+                rhs: d_r,
+            },
+            rhs: DMul {
+                lhs: d_l,
+                rhs: self.rhs.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DNeg<A> {
+    pub arg: A,
+}
+
+impl<A> fmt::Display for DNeg<A>
+where
+    A: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(-{})", self.arg)
+    }
+}
+
+impl<A> Eval for DNeg<A>
+where
+    A: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        -self.arg.eval(inputs)
+    }
+}
+
+impl<A> Derive for DNeg<A>
+where
+    A: Derive,
+{
+    type DerivT = DNeg<A::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DNeg {
+            arg: self.arg.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DSub<L, R> {
+    pub lhs: L,
+    pub rhs: R,
+}
+
+impl<L, R> fmt::Display for DSub<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} - {})", self.lhs, self.rhs)
+    }
+}
+
+impl<L, R> Eval for DSub<L, R>
+where
+    L: Eval,
+    R: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.lhs.eval(inputs) - self.rhs.eval(inputs)
+    }
+}
+
+impl<L, R> Derive for DSub<L, R>
+where
+    L: Derive,
+    R: Derive,
+{
+    type DerivT = DSub<L::DerivT, R::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DSub {
+            lhs: self.lhs.deriv(vs),
+            rhs: self.rhs.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DDiv<L, R> {
+    pub lhs: L,
+    pub rhs: R,
+}
+
+impl<L, R> fmt::Display for DDiv<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} / {})", self.lhs, self.rhs)
+    }
+}
+
+impl<L, R> Eval for DDiv<L, R>
+where
+    L: Eval,
+    R: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.lhs.eval(inputs) / self.rhs.eval(inputs)
+    }
+}
+
+impl<L, R> Derive for DDiv<L, R>
+where
+    L: Derive,
+    R: Derive,
+{
+    type DerivT = DDiv<DSub<DMul<L::DerivT, R>, DMul<L, R::DerivT>>, DMul<R, R>>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        let d_l = self.lhs.deriv(vs);
+        let d_r = self.rhs.deriv(vs);
+        DDiv {
+            lhs: DSub {
+                lhs: DMul {
+                    lhs: d_l,
+                    rhs: self.rhs.clone(),
+                },
+                rhs: DMul {
+                    lhs: self.lhs.clone(),
+                    rhs: d_r,
+                },
+            },
+            rhs: DMul {
+                lhs: self.rhs.clone(),
+                rhs: self.rhs.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DPow<L, R> {
+    pub lhs: L,
+    pub rhs: R,
+}
+
+impl<L, R> fmt::Display for DPow<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} ^ {})", self.lhs, self.rhs)
+    }
+}
+
+impl<L, R> Eval for DPow<L, R>
+where
+    L: Eval,
+    R: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.lhs.eval(inputs).powf(self.rhs.eval(inputs))
+    }
+}
+
+/// Only the constant-exponent case `u^n` is differentiable for now: the
+/// general case would need `d/dx u^v = u^v (v' ln u + v u'/u)`, which drags
+/// in `DLn` for no benefit until a caller actually needs a non-constant
+/// exponent.
+impl<L> Derive for DPow<L, Const>
+where
+    L: Derive,
+{
+    type DerivT = DMul<DMul<Const, DPow<L, Const>>, L::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        let n = self.rhs.val;
+        DMul {
+            lhs: DMul {
+                lhs: Const { val: n },
+                rhs: DPow {
+                    lhs: self.lhs.clone(),
+                    rhs: Const { val: n - 1.0 },
+                },
+            },
+            rhs: self.lhs.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DExp<A> {
+    pub arg: A,
+}
+
+impl<A> fmt::Display for DExp<A>
+where
+    A: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exp({})", self.arg)
+    }
+}
+
+impl<A> Eval for DExp<A>
+where
+    A: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.arg.eval(inputs).exp()
+    }
+}
+
+impl<A> Derive for DExp<A>
+where
+    A: Derive,
+{
+    type DerivT = DMul<DExp<A>, A::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DMul {
+            lhs: DExp {
+                arg: self.arg.clone(),
+            },
+            rhs: self.arg.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DLn<A> {
+    pub arg: A,
+}
+
+impl<A> fmt::Display for DLn<A>
+where
+    A: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ln({})", self.arg)
+    }
+}
+
+impl<A> Eval for DLn<A>
+where
+    A: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.arg.eval(inputs).ln()
+    }
+}
+
+impl<A> Derive for DLn<A>
+where
+    A: Derive,
+{
+    type DerivT = DMul<DDiv<Const, A>, A::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DMul {
+            lhs: DDiv {
+                lhs: Const { val: 1.0 },
+                rhs: self.arg.clone(),
+            },
+            rhs: self.arg.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DSin<A> {
+    pub arg: A,
+}
+
+impl<A> fmt::Display for DSin<A>
+where
+    A: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sin({})", self.arg)
+    }
+}
+
+impl<A> Eval for DSin<A>
+where
+    A: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.arg.eval(inputs).sin()
+    }
+}
+
+impl<A> Derive for DSin<A>
+where
+    A: Derive,
+{
+    type DerivT = DMul<DCos<A>, A::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DMul {
+            lhs: DCos {
+                arg: self.arg.clone(),
+            },
+            rhs: self.arg.deriv(vs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DCos<A> {
+    pub arg: A,
+}
+
+impl<A> fmt::Display for DCos<A>
+where
+    A: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cos({})", self.arg)
+    }
+}
+
+impl<A> Eval for DCos<A>
+where
+    A: Eval,
+{
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        self.arg.eval(inputs).cos()
+    }
+}
+
+impl<A> Derive for DCos<A>
+where
+    A: Derive,
+{
+    type DerivT = DMul<DNeg<DSin<A>>, A::DerivT>;
+
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT {
+        DMul {
+            lhs: DNeg {
+                arg: DSin {
+                    arg: self.arg.clone(),
+                },
+            },
+            rhs: self.arg.deriv(vs),
+        }
+    }
+}
+
+/// Implements `std::ops::{Add,Sub,Mul,Div,Neg}` for a node type in terms of
+/// the corresponding `D*` struct, so expressions can be built as
+/// `a.clone() + b` instead of spelling out `DAdd { lhs: a, rhs: b }`.
+macro_rules! impl_ops {
+    ($ty:ident $(< $($g:ident),+ >)?) => {
+        impl<$($($g,)+)? Rhs: Eval> ops::Add<Rhs> for $ty $(<$($g),+>)? {
+            type Output = DAdd<Self, Rhs>;
+            fn add(self, rhs: Rhs) -> Self::Output {
+                DAdd { lhs: self, rhs }
+            }
+        }
+
+        impl<$($($g,)+)? Rhs: Eval> ops::Sub<Rhs> for $ty $(<$($g),+>)? {
+            type Output = DSub<Self, Rhs>;
+            fn sub(self, rhs: Rhs) -> Self::Output {
+                DSub { lhs: self, rhs }
+            }
+        }
+
+        impl<$($($g,)+)? Rhs: Eval> ops::Mul<Rhs> for $ty $(<$($g),+>)? {
+            type Output = DMul<Self, Rhs>;
+            fn mul(self, rhs: Rhs) -> Self::Output {
+                DMul { lhs: self, rhs }
+            }
+        }
+
+        impl<$($($g,)+)? Rhs: Eval> ops::Div<Rhs> for $ty $(<$($g),+>)? {
+            type Output = DDiv<Self, Rhs>;
+            fn div(self, rhs: Rhs) -> Self::Output {
+                DDiv { lhs: self, rhs }
+            }
+        }
+
+        impl$(<$($g),+>)? ops::Neg for $ty $(<$($g),+>)? {
+            type Output = DNeg<Self>;
+            fn neg(self) -> Self::Output {
+                DNeg { arg: self }
+            }
+        }
+    };
+}
+
+impl_ops!(Const);
+impl_ops!(Symbol);
+impl_ops!(DAdd<L, R>);
+impl_ops!(DMul<L, R>);
+impl_ops!(DNeg<A>);
+impl_ops!(DSub<L, R>);
+impl_ops!(DDiv<L, R>);
+impl_ops!(DPow<L, R>);
+impl_ops!(DExp<A>);
+impl_ops!(DLn<A>);
+impl_ops!(DSin<A>);
+impl_ops!(DCos<A>);
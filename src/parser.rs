@@ -0,0 +1,273 @@
+use std::fmt;
+
+use crate::expr::Expr;
+use crate::symbol::Symbol;
+
+/// Why a string failed to parse as an [`Expr`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseError::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            ParseError::UnexpectedToken(tok) => write!(f, "unexpected token '{}'", tok),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut s = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    s.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let val = s
+                .parse::<f64>()
+                .map_err(|_| ParseError::UnexpectedToken(s.clone()))?;
+            tokens.push(Token::Num(val));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut s = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    s.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(s));
+        } else {
+            chars.next();
+            tokens.push(match c {
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '*' => Token::Star,
+                '/' => Token::Slash,
+                '^' => Token::Caret,
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                other => return Err(ParseError::UnexpectedChar(other)),
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive-descent parser over the token stream produced by [`lex`].
+/// Precedence, loosest to tightest: `+ -`, then `* /`, then unary `-`,
+/// then `^` (right-associative).
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, tok: &Token) -> Result<(), ParseError> {
+        match self.advance() {
+            Some(ref t) if t == tok => Ok(()),
+            Some(t) => Err(ParseError::UnexpectedToken(format!("{:?}", t))),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.advance();
+                    lhs = lhs + self.parse_term()?;
+                }
+                Some(Token::Minus) => {
+                    self.advance();
+                    lhs = lhs - self.parse_term()?;
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.advance();
+                    lhs = lhs * self.parse_unary()?;
+                }
+                Some(Token::Slash) => {
+                    self.advance();
+                    lhs = lhs / self.parse_unary()?;
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        if let Some(Token::Minus) = self.peek() {
+            self.advance();
+            return Ok(-self.parse_unary()?);
+        }
+        self.parse_pow()
+    }
+
+    fn parse_pow(&mut self) -> Result<Expr, ParseError> {
+        let base = self.parse_atom()?;
+        if let Some(Token::Caret) = self.peek() {
+            self.advance();
+            // Right-associative: `2^3^2 == 2^(3^2)`.
+            let exp = self.parse_unary()?;
+            return Ok(base.pow(exp));
+        }
+        Ok(base)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, ParseError> {
+        match self.advance() {
+            Some(Token::Num(val)) => Ok(Expr::Const(val)),
+            Some(Token::LParen) => {
+                let inner = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LParen) = self.peek() {
+                    self.advance();
+                    let arg = self.parse_expr()?;
+                    self.expect(&Token::RParen)?;
+                    match name.as_str() {
+                        "exp" => Ok(Expr::Exp(Box::new(arg))),
+                        "ln" => Ok(Expr::Ln(Box::new(arg))),
+                        "sin" => Ok(Expr::Sin(Box::new(arg))),
+                        "cos" => Ok(Expr::Cos(Box::new(arg))),
+                        other => Err(ParseError::UnexpectedToken(other.to_string())),
+                    }
+                } else {
+                    Ok(Expr::Symbol(Symbol::new(&name)))
+                }
+            }
+            Some(tok) => Err(ParseError::UnexpectedToken(format!("{:?}", tok))),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+}
+
+/// Parses a string like `"(x + 1) * (x + 1)"` or `"sin(x) * exp(y)"` into an
+/// [`Expr`].
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let tokens = lex(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(ParseError::UnexpectedToken(format!(
+            "{:?}",
+            parser.tokens[parser.pos]
+        )));
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::symbol::{Eval, Inputs, Symbol};
+    use std::collections::HashMap;
+
+    fn eval_at(src: &str, assignments: &[(&str, f64)]) -> f64 {
+        let symbol_map = assignments
+            .iter()
+            .map(|(name, val)| (Symbol::new(name), *val))
+            .collect::<HashMap<_, _>>();
+        parse(src).unwrap().eval(&Inputs { symbol_map })
+    }
+
+    #[test]
+    fn add_sub_binds_looser_than_mul_div() {
+        assert_eq!(eval_at("2 + 3 * 4", &[]), 14.0);
+        assert_eq!(eval_at("2 * 3 + 4", &[]), 10.0);
+        assert_eq!(eval_at("10 - 4 / 2", &[]), 8.0);
+    }
+
+    #[test]
+    fn pow_is_right_associative() {
+        // 2^(3^2) == 2^9 == 512, not (2^3)^2 == 64.
+        assert_eq!(eval_at("2^3^2", &[]), 512.0);
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_pow() {
+        // -(x^2), not (-x)^2.
+        assert_eq!(eval_at("-x^2", &[("x", 3.0)]), -9.0);
+    }
+
+    #[test]
+    fn pow_accepts_a_negative_exponent() {
+        assert_eq!(eval_at("2^-3", &[]), 0.125);
+    }
+
+    #[test]
+    fn parenthesization_overrides_precedence() {
+        assert_eq!(eval_at("(2 + 3) * 4", &[]), 20.0);
+    }
+
+    #[test]
+    fn parses_function_calls() {
+        assert_eq!(eval_at("sin(0) + cos(0)", &[]), 1.0);
+    }
+
+    #[test]
+    fn rejects_trailing_garbage() {
+        assert!(parse("1 + 2)").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_function() {
+        assert!(parse("tan(x)").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::expr::Expr;
+
+/// Bottom-up rewrite to a fixpoint: recurse into children first, then apply
+/// local rules (constant folding, additive/multiplicative identities).
+/// Symbolic derivatives otherwise balloon into unreadable, wastefully
+/// evaluated trees like `((x+1)*0 + 1*(x+1))`.
+pub fn simplify(expr: &Expr) -> Expr {
+    let mut current = expr.clone();
+    loop {
+        let (next, changed) = simplify_once(&current);
+        if !changed {
+            return next;
+        }
+        current = next;
+    }
+}
+
+/// Runs one bottom-up pass over the whole tree and reports whether anything
+/// was rewritten, so the
+/// fixpoint loop above can stop based on that flag rather than comparing
+/// trees with `==` -- a constant-folded NaN (e.g. from `ln(-1)`) is never
+/// equal to itself, which would otherwise spin the loop forever.
+fn simplify_once(expr: &Expr) -> (Expr, bool) {
+    match expr {
+        Expr::Const(_) | Expr::Symbol(_) => (expr.clone(), false),
+        Expr::Add(lhs, rhs) => simplify_binary(expr, lhs, rhs),
+        Expr::Sub(lhs, rhs) => simplify_binary(expr, lhs, rhs),
+        Expr::Mul(lhs, rhs) => simplify_binary(expr, lhs, rhs),
+        Expr::Div(lhs, rhs) => simplify_binary(expr, lhs, rhs),
+        Expr::Pow(lhs, rhs) => simplify_binary(expr, lhs, rhs),
+        Expr::Neg(arg) => simplify_unary(expr, arg),
+        Expr::Exp(arg) => simplify_unary(expr, arg),
+        Expr::Ln(arg) => simplify_unary(expr, arg),
+        Expr::Sin(arg) => simplify_unary(expr, arg),
+        Expr::Cos(arg) => simplify_unary(expr, arg),
+    }
+}
+
+fn simplify_binary(orig: &Expr, lhs: &Expr, rhs: &Expr) -> (Expr, bool) {
+    let (lhs, lhs_changed) = simplify_once(lhs);
+    let (rhs, rhs_changed) = simplify_once(rhs);
+
+    if let (Expr::Const(l), Expr::Const(r)) = (&lhs, &rhs) {
+        // Leave `c / 0` unfolded rather than producing an `f64::INFINITY`
+        // or `NAN` constant that silently poisons later arithmetic.
+        if !(matches!(orig, Expr::Div(..)) && *r == 0.0) {
+            return (Expr::Const(fold(orig, *l, *r)), true);
+        }
+    }
+
+    let (result, rewrote) = match orig {
+        Expr::Add(..) => match (&lhs, &rhs) {
+            (Expr::Const(l), _) if *l == 0.0 => (rhs, true),
+            (_, Expr::Const(r)) if *r == 0.0 => (lhs, true),
+            _ => (Expr::Add(Box::new(lhs), Box::new(rhs)), false),
+        },
+        Expr::Sub(..) => match &rhs {
+            Expr::Const(r) if *r == 0.0 => (lhs, true),
+            _ => (Expr::Sub(Box::new(lhs), Box::new(rhs)), false),
+        },
+        Expr::Mul(..) => match (&lhs, &rhs) {
+            (Expr::Const(l), _) if *l == 0.0 => (Expr::Const(0.0), true),
+            (_, Expr::Const(r)) if *r == 0.0 => (Expr::Const(0.0), true),
+            (Expr::Const(l), _) if *l == 1.0 => (rhs, true),
+            (_, Expr::Const(r)) if *r == 1.0 => (lhs, true),
+            _ => (Expr::Mul(Box::new(lhs), Box::new(rhs)), false),
+        },
+        Expr::Div(..) => match &rhs {
+            Expr::Const(r) if *r == 1.0 => (lhs, true),
+            _ => (Expr::Div(Box::new(lhs), Box::new(rhs)), false),
+        },
+        Expr::Pow(..) => match &rhs {
+            Expr::Const(r) if *r == 0.0 => (Expr::Const(1.0), true),
+            Expr::Const(r) if *r == 1.0 => (lhs, true),
+            _ => (Expr::Pow(Box::new(lhs), Box::new(rhs)), false),
+        },
+        _ => unreachable!("simplify_binary called with a non-binary node"),
+    };
+
+    (result, rewrote || lhs_changed || rhs_changed)
+}
+
+fn fold(orig: &Expr, l: f64, r: f64) -> f64 {
+    match orig {
+        Expr::Add(..) => l + r,
+        Expr::Sub(..) => l - r,
+        Expr::Mul(..) => l * r,
+        Expr::Div(..) => l / r,
+        Expr::Pow(..) => l.powf(r),
+        _ => unreachable!("fold called with a non-binary node"),
+    }
+}
+
+fn simplify_unary(orig: &Expr, arg: &Expr) -> (Expr, bool) {
+    let (arg, arg_changed) = simplify_once(arg);
+
+    if let Expr::Const(val) = arg {
+        let folded = match orig {
+            Expr::Neg(_) => -val,
+            Expr::Exp(_) => val.exp(),
+            Expr::Ln(_) => val.ln(),
+            Expr::Sin(_) => val.sin(),
+            Expr::Cos(_) => val.cos(),
+            _ => unreachable!("simplify_unary called with a non-unary node"),
+        };
+        return (Expr::Const(folded), true);
+    }
+
+    let result = match orig {
+        Expr::Neg(_) => Expr::Neg(Box::new(arg)),
+        Expr::Exp(_) => Expr::Exp(Box::new(arg)),
+        Expr::Ln(_) => Expr::Ln(Box::new(arg)),
+        Expr::Sin(_) => Expr::Sin(Box::new(arg)),
+        Expr::Cos(_) => Expr::Cos(Box::new(arg)),
+        _ => unreachable!("simplify_unary called with a non-unary node"),
+    };
+    (result, arg_changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::simplify;
+    use crate::expr::Expr;
+    use crate::symbol::Symbol;
+
+    fn x() -> Expr {
+        Expr::Symbol(Symbol::new("x"))
+    }
+
+    #[test]
+    fn folds_constants() {
+        let e = Expr::Const(2.0) + Expr::Const(3.0);
+        assert_eq!(simplify(&e), Expr::Const(5.0));
+    }
+
+    #[test]
+    fn removes_additive_identity() {
+        assert_eq!(simplify(&(x() + Expr::Const(0.0))), x());
+        assert_eq!(simplify(&(Expr::Const(0.0) + x())), x());
+    }
+
+    #[test]
+    fn removes_multiplicative_identity() {
+        assert_eq!(simplify(&(x() * Expr::Const(1.0))), x());
+        assert_eq!(simplify(&(Expr::Const(1.0) * x())), x());
+        assert_eq!(simplify(&(x() * Expr::Const(0.0))), Expr::Const(0.0));
+    }
+
+    #[test]
+    fn removes_pow_identities() {
+        assert_eq!(simplify(&x().pow(Expr::Const(1.0))), x());
+        assert_eq!(simplify(&x().pow(Expr::Const(0.0))), Expr::Const(1.0));
+    }
+
+    #[test]
+    fn cascades_to_a_fixpoint() {
+        // ((x + 1) * 0 + 1 * (x + 1)) should collapse all the way to (x + 1).
+        let xp1 = x() + Expr::Const(1.0);
+        let e = (xp1.clone() * Expr::Const(0.0)) + (Expr::Const(1.0) * xp1.clone());
+        assert_eq!(simplify(&e), xp1);
+    }
+
+    #[test]
+    fn leaves_division_by_zero_unfolded() {
+        let e = Expr::Const(1.0) / Expr::Const(0.0);
+        assert_eq!(simplify(&e), e);
+    }
+
+    #[test]
+    fn terminates_on_out_of_domain_constants() {
+        // ln(-1) folds to a NaN constant; the fixpoint check must not rely
+        // on `NaN == NaN` (which is always false) to detect "no change".
+        let e = x() * Expr::Ln(Box::new(Expr::Const(-1.0)));
+        let simplified = simplify(&e);
+        match simplified {
+            Expr::Mul(lhs, rhs) => {
+                assert_eq!(*lhs, x());
+                assert!(matches!(*rhs, Expr::Const(v) if v.is_nan()));
+            }
+            other => panic!("expected a Mul node, got {:?}", other),
+        }
+    }
+}
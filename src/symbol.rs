@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Symbol {
+    pub name: String,
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Self {
+        Symbol { name: name.to_string() }
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+pub struct Inputs {
+    pub symbol_map: HashMap<Symbol, f64>,
+}
+
+pub trait Eval: Clone {
+    fn eval(&self, inputs: &Inputs) -> f64;
+}
+
+pub trait Derive: Eval {
+    type DerivT: Eval;
+    fn deriv(&self, vs: &Symbol) -> Self::DerivT;
+}
+
+impl Eval for Symbol {
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        if let Some(res) = inputs.symbol_map.get(self) {
+            *res
+        } else {
+            panic!("Couldn't find symbol {:?}", self)
+        }
+    }
+}